@@ -1,19 +1,60 @@
 // Model manager for built-in AI models - handles downloads and lifecycle
 // Follows the same pattern as whisper_engine/whisper_engine.rs for consistency
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::{anyhow, Result};
+use rand::Rng;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore};
 
 use super::models::{get_available_models, get_model_by_name};
 
+/// Default number of retry attempts for a transient download failure
+const DEFAULT_MAX_RETRIES: u32 = 5;
+
+/// Default number of downloads the scheduler runs at the same time
+const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 2;
+
+/// Base delay for the exponential backoff between download retries
+const RETRY_BASE_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Upper bound on the exponential backoff delay, before jitter is added
+const RETRY_MAX_DELAY: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Default interval between background repair/scrub passes
+const DEFAULT_REPAIR_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Default for whether the repair task auto-redownloads corrupted models
+const DEFAULT_AUTO_REDOWNLOAD: bool = true;
+
+/// Delay between re-verifying each model during a repair pass, so scrubbing
+/// several large GGUF files back-to-back doesn't thrash the disk
+const REPAIR_THROTTLE_DELAY: Duration = Duration::from_millis(250);
+
+/// Outcome of a single request-and-stream attempt inside `download_model`
+enum DownloadAttemptError {
+    /// The user cancelled the download; do not retry
+    Cancelled,
+    /// A transient failure (timeout, 5xx, connection reset, ...); safe to retry
+    Retryable {
+        error: anyhow::Error,
+        retry_after: Option<std::time::Duration>,
+    },
+    /// This host/URL can't serve the file (404, unreachable, ...), but a
+    /// different mirror might still work
+    HostFatal(anyhow::Error),
+    /// A local failure (disk I/O, ...) that no mirror can fix; abort entirely
+    Fatal(anyhow::Error),
+}
+
 // ============================================================================
 // Model Status Types
 // ============================================================================
@@ -25,14 +66,30 @@ pub enum ModelStatus {
     /// Model is not yet downloaded
     NotDownloaded,
 
+    /// Model is queued for download, waiting on a concurrency permit
+    Queued,
+
     /// Model is currently being downloaded (progress 0-100)
     Downloading { progress: u8 },
 
     /// Model is downloaded and ready to use
     Available,
 
+    /// Download was interrupted but a resumable `.part` file is on disk
+    Paused { downloaded_bytes: u64, total_bytes: u64 },
+
+    /// Re-verification failed; the model is being automatically re-downloaded
+    Repairing,
+
     /// Model file is corrupted and needs redownload
-    Corrupted { file_size: u64, expected_min_size: u64 },
+    Corrupted {
+        file_size: u64,
+        expected_min_size: u64,
+        /// SHA-256 actually found on disk, when a deep verify was performed
+        computed_sha256: Option<String>,
+        /// SHA-256 the model definition expects
+        expected_sha256: Option<String>,
+    },
 
     /// Error occurred with the model
     Error(String),
@@ -64,12 +121,31 @@ pub struct ModelInfo {
 
     /// GGUF filename on disk
     pub gguf_file: String,
+
+    /// Unix timestamp (seconds) of the last time this model passed verification,
+    /// set by a deep-verify scan or the background repair task
+    pub last_verified: Option<u64>,
+
+    /// The URL (primary or mirror) the current file was actually downloaded from,
+    /// for diagnostics
+    pub successful_mirror: Option<String>,
+}
+
+/// Snapshot of the download scheduler for UI display
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadQueueStatus {
+    /// Model names waiting on a concurrency permit, in the order they'll start
+    pub queued: Vec<String>,
+
+    /// Model names currently downloading
+    pub in_flight: HashSet<String>,
 }
 
 // ============================================================================
 // Model Manager
 // ============================================================================
 
+#[derive(Clone)]
 pub struct ModelManager {
     /// Directory where models are stored
     models_dir: PathBuf,
@@ -80,8 +156,30 @@ pub struct ModelManager {
     /// Active downloads (model names)
     active_downloads: Arc<RwLock<HashSet<String>>>,
 
-    /// Cancellation flag for current download
-    cancel_download_flag: Arc<RwLock<Option<String>>>,
+    /// Models waiting on a download permit, in FIFO order
+    download_queue: Arc<RwLock<VecDeque<String>>>,
+
+    /// Bounds how many downloads run at once; queued downloads wait on a permit
+    download_semaphore: Arc<Semaphore>,
+
+    /// Per-model cancellation flags, so cancelling one model's download can't
+    /// race against another model downloading concurrently
+    cancel_flags: Arc<RwLock<HashMap<String, bool>>>,
+
+    /// Maximum number of retry attempts for a transient download failure
+    max_retries: u32,
+
+    /// Maximum number of downloads the scheduler runs at the same time
+    max_concurrent_downloads: usize,
+
+    /// How often the background repair task re-verifies models
+    repair_interval: Duration,
+
+    /// Whether the repair task automatically re-downloads a model it finds corrupted
+    auto_redownload: bool,
+
+    /// Unix timestamp (seconds) the last repair pass finished, if one has run
+    last_scrub: Arc<RwLock<Option<u64>>>,
 }
 
 impl ModelManager {
@@ -123,10 +221,42 @@ impl ModelManager {
             models_dir,
             available_models: Arc::new(RwLock::new(HashMap::new())),
             active_downloads: Arc::new(RwLock::new(HashSet::new())),
-            cancel_download_flag: Arc::new(RwLock::new(None)),
+            download_queue: Arc::new(RwLock::new(VecDeque::new())),
+            download_semaphore: Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT_DOWNLOADS)),
+            cancel_flags: Arc::new(RwLock::new(HashMap::new())),
+            max_retries: DEFAULT_MAX_RETRIES,
+            max_concurrent_downloads: DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+            repair_interval: DEFAULT_REPAIR_INTERVAL,
+            auto_redownload: DEFAULT_AUTO_REDOWNLOAD,
+            last_scrub: Arc::new(RwLock::new(None)),
         })
     }
 
+    /// Set the maximum number of retry attempts for a transient download failure
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set how many downloads the scheduler runs at the same time
+    pub fn with_max_concurrent_downloads(mut self, max_concurrent_downloads: usize) -> Self {
+        self.max_concurrent_downloads = max_concurrent_downloads;
+        self.download_semaphore = Arc::new(Semaphore::new(max_concurrent_downloads));
+        self
+    }
+
+    /// Set how often the background repair task re-verifies models
+    pub fn with_repair_interval(mut self, repair_interval: Duration) -> Self {
+        self.repair_interval = repair_interval;
+        self
+    }
+
+    /// Set whether the repair task automatically re-downloads a model it finds corrupted
+    pub fn with_auto_redownload(mut self, auto_redownload: bool) -> Self {
+        self.auto_redownload = auto_redownload;
+        self
+    }
+
     /// Initialize and scan for existing models
     pub async fn init(&self) -> Result<()> {
         // Create models directory if it doesn't exist
@@ -138,23 +268,52 @@ impl ModelManager {
         // Scan for existing models
         self.scan_models().await?;
 
+        // Keep the model store healthy in the background without user intervention
+        self.spawn_repair_loop();
+
         Ok(())
     }
 
-    /// Scan models directory and update status
+    /// Scan models directory and update status, trusting file size within tolerance
     pub async fn scan_models(&self) -> Result<()> {
+        self.scan_models_with_options(false).await
+    }
+
+    /// Scan models directory, rehashing every on-disk file instead of trusting its
+    /// size. Slower (reads every model fully) but catches a truncated or tampered
+    /// file that happens to land within the size tolerance.
+    pub async fn scan_models_deep_verify(&self) -> Result<()> {
+        self.scan_models_with_options(true).await
+    }
+
+    async fn scan_models_with_options(&self, deep_verify: bool) -> Result<()> {
         let start = std::time::Instant::now();
 
         log::info!(
-            "Starting model scan in directory: {}",
-            self.models_dir.display()
+            "Starting model scan in directory: {} (deep_verify={})",
+            self.models_dir.display(),
+            deep_verify
         );
 
         let model_defs = get_available_models();
         let mut models_map = HashMap::new();
 
+        // A scan rebuilds every `ModelInfo` from the filesystem, but
+        // `last_verified` (stamped by the repair task) and `successful_mirror`
+        // (stamped after a download) aren't derivable from the filesystem -
+        // carry them over from the previous snapshot so a routine scan
+        // (`init`, `is_model_ready(refresh=true)`, ...) doesn't erase them.
+        let previous_state: HashMap<String, (Option<u64>, Option<String>)> = self
+            .available_models
+            .read()
+            .await
+            .iter()
+            .map(|(name, info)| (name.clone(), (info.last_verified, info.successful_mirror.clone())))
+            .collect();
+
         for model_def in model_defs {
             let model_path = self.models_dir.join(&model_def.gguf_file);
+            let part_path = Self::part_path(&model_path);
             log::debug!(
                 "Checking model '{}' at path: {}",
                 model_def.name,
@@ -162,8 +321,34 @@ impl ModelManager {
             );
 
             let status = if model_path.exists() {
-                // Check if file size matches expected size (basic validation)
                 match fs::metadata(&model_path).await {
+                    Ok(metadata) if deep_verify => {
+                        match Self::hash_file(&model_path).await {
+                            Ok(computed) if computed == model_def.expected_sha256 => {
+                                log::info!("Model '{}': AVAILABLE (hash verified)", model_def.name);
+                                ModelStatus::Available
+                            }
+                            Ok(computed) => {
+                                log::warn!(
+                                    "Model '{}': CORRUPTED (hash mismatch: computed {}, expected {})",
+                                    model_def.name,
+                                    computed,
+                                    model_def.expected_sha256
+                                );
+                                ModelStatus::Corrupted {
+                                    file_size: metadata.len() / (1024 * 1024),
+                                    expected_min_size: model_def.size_mb,
+                                    computed_sha256: Some(computed),
+                                    expected_sha256: Some(model_def.expected_sha256.clone()),
+                                }
+                            }
+                            Err(e) => {
+                                log::error!("Model '{}': Failed to hash file: {}", model_def.name, e);
+                                ModelStatus::Error(format!("Failed to hash file: {}", e))
+                            }
+                        }
+                    }
+                    // Check if file size matches expected size (basic validation)
                     Ok(metadata) => {
                         let file_size_mb = metadata.len() / (1024 * 1024);
 
@@ -192,6 +377,8 @@ impl ModelManager {
                             ModelStatus::Corrupted {
                                 file_size: file_size_mb,
                                 expected_min_size: expected_min,
+                                computed_sha256: None,
+                                expected_sha256: None,
                             }
                         }
                     }
@@ -204,11 +391,43 @@ impl ModelManager {
                         ModelStatus::Error(format!("Failed to read metadata: {}", e))
                     }
                 }
+            } else if let Ok(part_metadata) = fs::metadata(&part_path).await {
+                let downloaded_bytes = part_metadata.len();
+                let total_bytes = model_def.size_mb * 1024 * 1024;
+                log::info!(
+                    "Model '{}': PAUSED (orphaned .part file, {} / {} bytes)",
+                    model_def.name,
+                    downloaded_bytes,
+                    total_bytes
+                );
+                ModelStatus::Paused {
+                    downloaded_bytes,
+                    total_bytes,
+                }
             } else {
                 log::debug!("Model '{}': NOT FOUND", model_def.name);
                 ModelStatus::NotDownloaded
             };
 
+            let (prev_last_verified, prev_successful_mirror) = previous_state
+                .get(&model_def.name)
+                .cloned()
+                .unwrap_or((None, None));
+
+            // A deep-verify scan just re-hashed the file, so it's authoritative:
+            // stamp it as just-verified, or clear the stamp if that re-hash
+            // disproved it. A shallow (size-only) scan can't confirm or refute
+            // a prior deep verification, so it leaves the existing stamp alone.
+            let last_verified = if deep_verify {
+                if status == ModelStatus::Available {
+                    Some(Self::now_unix())
+                } else {
+                    None
+                }
+            } else {
+                prev_last_verified
+            };
+
             let model_info = ModelInfo {
                 name: model_def.name.clone(),
                 display_name: model_def.display_name.clone(),
@@ -218,6 +437,8 @@ impl ModelManager {
                 context_size: model_def.context_size,
                 description: model_def.description.clone(),
                 gguf_file: model_def.gguf_file.clone(),
+                last_verified,
+                successful_mirror: prev_successful_mirror,
             };
 
             models_map.insert(model_def.name.clone(), model_info);
@@ -300,10 +521,11 @@ impl ModelManager {
             active.insert(model_name.to_string());
         }
 
-        // Clear cancellation flag
+        // Clear this model's cancellation flag without touching any other
+        // model's download that might be running concurrently
         {
-            let mut cancel_flag = self.cancel_download_flag.write().await;
-            *cancel_flag = None;
+            let mut cancel_flags = self.cancel_flags.write().await;
+            cancel_flags.insert(model_name.to_string(), false);
         }
 
         // Update status to downloading
@@ -320,37 +542,339 @@ impl ModelManager {
         }
 
         let file_path = self.models_dir.join(&model_def.gguf_file);
+        let part_path = Self::part_path(&file_path);
 
         log::info!("Downloading from: {}", model_def.download_url);
-        log::info!("Saving to: {}", file_path.display());
+        log::info!("Saving to: {} (via {})", file_path.display(), part_path.display());
 
         // Create models directory if needed
         if !self.models_dir.exists() {
             fs::create_dir_all(&self.models_dir).await?;
         }
 
-        // Download the file
+        // Download the file, trying the primary URL then each mirror in order.
+        // Within a single URL, transient failures are retried with exponential
+        // backoff; a URL is abandoned (in favor of the next mirror) once its
+        // retries are exhausted or it returns a host-level error. Every attempt
+        // re-reads the current .part file length and resumes the range request
+        // from there, so neither a retry nor a mirror switch discards bytes
+        // already on disk.
+        let mut urls: Vec<String> = Vec::with_capacity(1 + model_def.mirror_urls.len());
+        urls.push(model_def.download_url.clone());
+        urls.extend(model_def.mirror_urls.iter().cloned());
+
         let client = Client::new();
-        let response = client
-            .get(&model_def.download_url)
-            .send()
+        let mut mirror_failures: Vec<String> = Vec::new();
+        let mut succeeded: Option<(u64, u64, Sha256, String)> = None;
+
+        'mirrors: for url in &urls {
+            let mut attempt: u32 = 0;
+            loop {
+                match self
+                    .stream_attempt(&client, model_name, url, &part_path, &progress_callback)
+                    .await
+                {
+                    Ok((downloaded, total_size, hasher)) => {
+                        succeeded = Some((downloaded, total_size, hasher, url.clone()));
+                        break 'mirrors;
+                    }
+                    Err(DownloadAttemptError::Cancelled) => {
+                        return Err(anyhow!("Download cancelled"));
+                    }
+                    Err(DownloadAttemptError::Fatal(error)) => {
+                        log::error!("Download failed fatally for '{}': {}", model_name, error);
+
+                        {
+                            let mut models = self.available_models.write().await;
+                            if let Some(model_info) = models.get_mut(model_name) {
+                                model_info.status = ModelStatus::Error(error.to_string());
+                            }
+                        }
+
+                        let mut active = self.active_downloads.write().await;
+                        active.remove(model_name);
+
+                        return Err(error);
+                    }
+                    Err(DownloadAttemptError::HostFatal(error)) => {
+                        log::warn!("Mirror '{}' failed for '{}': {}", url, model_name, error);
+                        mirror_failures.push(format!("{}: {}", url, error));
+                        continue 'mirrors;
+                    }
+                    Err(DownloadAttemptError::Retryable { error, retry_after }) => {
+                        attempt += 1;
+                        if attempt > self.max_retries {
+                            log::warn!(
+                                "Mirror '{}' exhausted retries for '{}': {}",
+                                url,
+                                model_name,
+                                error
+                            );
+                            mirror_failures
+                                .push(format!("{}: {} (after {} attempts)", url, error, attempt));
+                            continue 'mirrors;
+                        }
+
+                        let delay = retry_after.unwrap_or_else(|| Self::backoff_delay(attempt));
+                        log::warn!(
+                            "Retryable error downloading '{}' from '{}' (attempt {}/{}): {}. Retrying in {:?}",
+                            model_name,
+                            url,
+                            attempt,
+                            self.max_retries,
+                            error,
+                            delay
+                        );
+                        tokio::time::sleep(delay).await;
+                    }
+                }
+            }
+        }
+
+        let (downloaded, total_size, hasher, mirror_url) = match succeeded {
+            Some(result) => result,
+            None => {
+                let message = format!(
+                    "All {} download URL(s) for '{}' failed: {}",
+                    urls.len(),
+                    model_name,
+                    mirror_failures.join("; ")
+                );
+                log::error!("{}", message);
+
+                {
+                    let mut models = self.available_models.write().await;
+                    if let Some(model_info) = models.get_mut(model_name) {
+                        model_info.status = ModelStatus::Error(message.clone());
+                    }
+                }
+
+                let mut active = self.active_downloads.write().await;
+                active.remove(model_name);
+
+                return Err(anyhow!(message));
+            }
+        };
+
+        log::info!(
+            "Download completed for model: {} ({} / {} bytes) via {}",
+            model_name,
+            downloaded,
+            total_size,
+            mirror_url
+        );
+
+        // Validate GGUF magic number on the staged .part file before it ever
+        // becomes the final path
+        if let Err(e) = self.validate_gguf_file(&part_path).await {
+            log::error!("Downloaded file failed validation: {}", e);
+
+            // Clean up invalid file
+            let _ = fs::remove_file(&part_path).await;
+
+            // Update status
+            {
+                let mut models = self.available_models.write().await;
+                if let Some(model_info) = models.get_mut(model_name) {
+                    model_info.status = ModelStatus::Error(format!("Validation failed: {}", e));
+                }
+            }
+
+            // Remove from active downloads
+            let mut active = self.active_downloads.write().await;
+            active.remove(model_name);
+
+            return Err(anyhow!("File validation failed: {}", e));
+        }
+
+        // Verify the SHA-256 computed while streaming against the model definition
+        let computed_sha256 = hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+
+        if computed_sha256 != model_def.expected_sha256 {
+            log::error!(
+                "Model '{}': hash mismatch (computed {}, expected {})",
+                model_name,
+                computed_sha256,
+                model_def.expected_sha256
+            );
+
+            let file_size = fs::metadata(&part_path)
+                .await
+                .map(|m| m.len() / (1024 * 1024))
+                .unwrap_or(0);
+            let _ = fs::remove_file(&part_path).await;
+
+            {
+                let mut models = self.available_models.write().await;
+                if let Some(model_info) = models.get_mut(model_name) {
+                    model_info.status = ModelStatus::Corrupted {
+                        file_size,
+                        expected_min_size: model_def.size_mb,
+                        computed_sha256: Some(computed_sha256.clone()),
+                        expected_sha256: Some(model_def.expected_sha256.clone()),
+                    };
+                }
+            }
+
+            let mut active = self.active_downloads.write().await;
+            active.remove(model_name);
+
+            return Err(anyhow!(
+                "SHA-256 mismatch: computed {}, expected {}",
+                computed_sha256,
+                model_def.expected_sha256
+            ));
+        }
+
+        // Only now that validation passed do we atomically promote the
+        // .part file to the final GGUF path
+        fs::rename(&part_path, &file_path)
             .await
-            .map_err(|e| anyhow!("Failed to start download: {}", e))?;
+            .map_err(|e| anyhow!("Failed to finalize downloaded file: {}", e))?;
+
+        // Update status to available
+        {
+            let mut models = self.available_models.write().await;
+            if let Some(model_info) = models.get_mut(model_name) {
+                model_info.status = ModelStatus::Available;
+                model_info.path = file_path.clone();
+                model_info.successful_mirror = Some(mirror_url.clone());
+            }
+        }
 
-        if !response.status().is_success() {
+        // Ensure 100% progress is reported
+        if let Some(ref callback) = progress_callback {
+            callback(100);
+        }
+
+        // Remove from active downloads
+        {
             let mut active = self.active_downloads.write().await;
             active.remove(model_name);
-            return Err(anyhow!("Download failed with status: {}", response.status()));
         }
 
-        let total_size = response.content_length().unwrap_or(0);
+        Ok(())
+    }
+
+    /// Perform a single request-and-stream pass, appending to (or truncating)
+    /// the `.part` file. Returns the bytes downloaded so far, the total size,
+    /// and a hasher covering every byte written to the `.part` file.
+    async fn stream_attempt(
+        &self,
+        client: &Client,
+        model_name: &str,
+        download_url: &str,
+        part_path: &PathBuf,
+        progress_callback: &Option<Box<dyn Fn(u8) + Send>>,
+    ) -> std::result::Result<(u64, u64, Sha256), DownloadAttemptError> {
+        // Resume from an existing .part file if one is present
+        let mut downloaded: u64 = fs::metadata(part_path)
+            .await
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+
+        if downloaded > 0 {
+            log::info!(
+                "Found existing .part file for '{}', attempting to resume from byte {}",
+                model_name,
+                downloaded
+            );
+        }
+
+        let mut request = client.get(download_url);
+        if downloaded > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", downloaded));
+        }
+
+        let response = request.send().await.map_err(|e| {
+            let error = anyhow!("Failed to start download: {}", e);
+            if Self::is_retryable_transport_error(&e) {
+                DownloadAttemptError::Retryable { error, retry_after: None }
+            } else {
+                // Can't even reach this host; a different mirror might fare better
+                DownloadAttemptError::HostFatal(error)
+            }
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            // A stale or oversized .part file (e.g. left over from a different
+            // build of the model, or from a server that never supported
+            // resume) makes every subsequent Range request 416. Treat that the
+            // same as a non-resumable 200: wipe the .part file and retry the
+            // same URL from byte zero instead of exhausting retries/mirrors
+            // and leaving the model permanently wedged.
+            if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+                log::warn!(
+                    "Range not satisfiable for '{}' at byte {}; discarding .part file and restarting from zero",
+                    model_name,
+                    downloaded
+                );
+                Self::truncate_part_file(part_path).await?;
+                return Err(DownloadAttemptError::Retryable {
+                    error: anyhow!("Range not satisfiable; restarting from zero"),
+                    retry_after: None,
+                });
+            }
+
+            return Err(if Self::retryable_status(status) {
+                DownloadAttemptError::Retryable {
+                    error: anyhow!("Download failed with status: {}", status),
+                    retry_after: Self::retry_after_delay(&response),
+                }
+            } else {
+                DownloadAttemptError::HostFatal(anyhow!("Download failed with status: {}", status))
+            });
+        }
+
+        // A server only resumes if it answers our Range request with 206 and a
+        // Content-Range that actually starts where our .part file left off.
+        // Anything else (e.g. 200 OK) means the server is sending the whole
+        // file again, so we truncate and restart from zero.
+        let resuming = downloaded > 0
+            && status == reqwest::StatusCode::PARTIAL_CONTENT
+            && Self::content_range_start(&response) == Some(downloaded);
+
+        if downloaded > 0 && !resuming {
+            log::warn!(
+                "Server did not honor resume request for '{}' (status {}); restarting from zero",
+                model_name,
+                status
+            );
+            downloaded = 0;
+        }
+
+        let total_size = match Self::content_range_total(&response) {
+            Some(total) => total,
+            None => downloaded + response.content_length().unwrap_or(0),
+        };
         log::info!("Total size: {} MB", total_size / (1024 * 1024));
 
-        let mut file = fs::File::create(&file_path)
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resuming)
+            .truncate(!resuming)
+            .open(part_path)
             .await
-            .map_err(|e| anyhow!("Failed to create file: {}", e))?;
+            .map_err(|e| DownloadAttemptError::Fatal(anyhow!("Failed to open .part file: {}", e)))?;
+
+        // Feed every chunk into the hasher as it's written so the whole file is
+        // never read a second time just to verify it. When resuming, the bytes
+        // already on disk have to be hashed once up front to keep the digest
+        // covering the complete file; stream them through in fixed-size chunks
+        // rather than loading the (potentially multi-GB) .part file into memory.
+        let mut hasher = Sha256::new();
+        if resuming {
+            Self::hash_into(part_path, &mut hasher).await.map_err(|e| {
+                DownloadAttemptError::Fatal(anyhow!("Failed to hash existing .part file: {}", e))
+            })?;
+        }
 
-        let mut downloaded: u64 = 0;
         let mut last_progress_report = 0u8;
         let mut last_report_time = std::time::Instant::now();
 
@@ -360,13 +884,13 @@ impl ModelManager {
         while let Some(chunk_result) = stream.next().await {
             // Check for cancellation
             {
-                let cancel_flag = self.cancel_download_flag.read().await;
-                if cancel_flag.as_ref() == Some(&model_name.to_string()) {
+                let cancel_flags = self.cancel_flags.read().await;
+                if cancel_flags.get(model_name).copied().unwrap_or(false) {
                     log::info!("Download cancelled for model: {}", model_name);
 
-                    // Clean up partial file
+                    // Keep the .part file on disk so the download can resume later
+                    file.flush().await.ok();
                     drop(file);
-                    let _ = fs::remove_file(&file_path).await;
 
                     // Remove from active downloads
                     let mut active = self.active_downloads.write().await;
@@ -376,18 +900,31 @@ impl ModelManager {
                     {
                         let mut models = self.available_models.write().await;
                         if let Some(model_info) = models.get_mut(model_name) {
-                            model_info.status = ModelStatus::NotDownloaded;
+                            model_info.status = ModelStatus::Paused {
+                                downloaded_bytes: downloaded,
+                                total_bytes: total_size,
+                            };
                         }
                     }
 
-                    return Err(anyhow!("Download cancelled"));
+                    return Err(DownloadAttemptError::Cancelled);
                 }
             }
 
-            let chunk = chunk_result.map_err(|e| anyhow!("Error reading chunk: {}", e))?;
-            file.write_all(&chunk)
-                .await
-                .map_err(|e| anyhow!("Error writing to file: {}", e))?;
+            let chunk = match chunk_result {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    return Err(DownloadAttemptError::Retryable {
+                        error: anyhow!("Error reading chunk: {}", e),
+                        retry_after: None,
+                    });
+                }
+            };
+
+            file.write_all(&chunk).await.map_err(|e| {
+                DownloadAttemptError::Fatal(anyhow!("Error writing to file: {}", e))
+            })?;
+            hasher.update(&chunk);
 
             downloaded += chunk.len() as u64;
 
@@ -429,51 +966,134 @@ impl ModelManager {
             }
         }
 
-        file.flush().await?;
+        file.flush()
+            .await
+            .map_err(|e| DownloadAttemptError::Fatal(anyhow!("Error flushing file: {}", e)))?;
         drop(file);
 
-        log::info!("Download completed for model: {}", model_name);
-
-        // Validate GGUF magic number
-        if let Err(e) = self.validate_gguf_file(&file_path).await {
-            log::error!("Downloaded file failed validation: {}", e);
-
-            // Clean up invalid file
-            let _ = fs::remove_file(&file_path).await;
+        Ok((downloaded, total_size, hasher))
+    }
 
-            // Update status
-            {
-                let mut models = self.available_models.write().await;
-                if let Some(model_info) = models.get_mut(model_name) {
-                    model_info.status = ModelStatus::Error(format!("Validation failed: {}", e));
-                }
-            }
+    /// Whether a `reqwest` transport-level error is worth retrying (timeouts,
+    /// connection resets, dropped stream chunks) as opposed to a programming
+    /// or configuration error.
+    fn is_retryable_transport_error(error: &reqwest::Error) -> bool {
+        error.is_timeout() || error.is_connect() || error.is_body() || error.is_decode()
+    }
 
-            // Remove from active downloads
-            let mut active = self.active_downloads.write().await;
-            active.remove(model_name);
+    /// HTTP statuses worth retrying: request timeout, rate limiting, and
+    /// upstream/gateway errors. Everything else (404, ...) is fatal. 416 is
+    /// handled separately in `stream_attempt` (it resets the .part file and
+    /// retries from zero rather than falling through to here).
+    fn retryable_status(status: reqwest::StatusCode) -> bool {
+        matches!(status.as_u16(), 408 | 429 | 500 | 502 | 503 | 504)
+    }
 
-            return Err(anyhow!("File validation failed: {}", e));
-        }
+    /// Truncate a `.part` file to zero bytes (creating it if absent), used to
+    /// discard a stale or oversized partial download before restarting it.
+    async fn truncate_part_file(part_path: &PathBuf) -> std::result::Result<(), DownloadAttemptError> {
+        fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(part_path)
+            .await
+            .map(|_| ())
+            .map_err(|e| {
+                DownloadAttemptError::Fatal(anyhow!("Failed to truncate stale .part file: {}", e))
+            })
+    }
 
-        // Update status to available
+    /// Honor a `Retry-After` header on 429/503 responses, when present
+    fn retry_after_delay(response: &reqwest::Response) -> Option<std::time::Duration> {
+        if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS
+            && response.status() != reqwest::StatusCode::SERVICE_UNAVAILABLE
         {
-            let mut models = self.available_models.write().await;
-            if let Some(model_info) = models.get_mut(model_name) {
-                model_info.status = ModelStatus::Available;
-                model_info.path = file_path.clone();
-            }
+            return None;
         }
+        let seconds: u64 = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)?
+            .to_str()
+            .ok()?
+            .parse()
+            .ok()?;
+        Some(std::time::Duration::from_secs(seconds))
+    }
 
-        // Ensure 100% progress is reported
-        if let Some(ref callback) = progress_callback {
-            callback(100);
-        }
+    /// Exponential backoff with jitter: base 500ms doubling each attempt, capped
+    /// at ~30s, plus 0-1000ms of random jitter so concurrent retries don't
+    /// stampede the server in lockstep.
+    fn backoff_delay(attempt: u32) -> std::time::Duration {
+        let exponential = RETRY_BASE_DELAY.saturating_mul(1u32 << attempt.min(6));
+        let capped = exponential.min(RETRY_MAX_DELAY);
+        let jitter = std::time::Duration::from_millis(rand::thread_rng().gen_range(0..1000));
+        capped + jitter
+    }
 
-        // Remove from active downloads
-        {
-            let mut active = self.active_downloads.write().await;
-            active.remove(model_name);
+    /// Path of the staging file a download is streamed into before being
+    /// atomically renamed to its final GGUF path
+    fn part_path(file_path: &PathBuf) -> PathBuf {
+        let mut part = file_path.clone().into_os_string();
+        part.push(".part");
+        PathBuf::from(part)
+    }
+
+    /// Parse the start offset out of a `Content-Range: bytes <start>-<end>/<total>` header
+    fn content_range_start(response: &reqwest::Response) -> Option<u64> {
+        let (start, _) = Self::parse_content_range(response)?;
+        Some(start)
+    }
+
+    /// Parse the total size out of a `Content-Range: bytes <start>-<end>/<total>` header
+    fn content_range_total(response: &reqwest::Response) -> Option<u64> {
+        let (_, total) = Self::parse_content_range(response)?;
+        Some(total)
+    }
+
+    /// Parse a `Content-Range` header into `(start, total)`
+    fn parse_content_range(response: &reqwest::Response) -> Option<(u64, u64)> {
+        let header = response.headers().get(reqwest::header::CONTENT_RANGE)?;
+        let value = header.to_str().ok()?;
+        Self::parse_content_range_value(value)
+    }
+
+    /// Parse a `Content-Range: bytes <start>-<end>/<total>` header value into
+    /// `(start, total)`. Split out from `parse_content_range` so the fallible
+    /// string parsing can be unit tested without a real `reqwest::Response`.
+    fn parse_content_range_value(value: &str) -> Option<(u64, u64)> {
+        let range = value.strip_prefix("bytes ")?;
+        let (range, total) = range.split_once('/')?;
+        let (start, _end) = range.split_once('-')?;
+        Some((start.parse().ok()?, total.parse().ok()?))
+    }
+
+    /// Stream a file's contents through SHA-256 and return the hex digest
+    async fn hash_file(path: &PathBuf) -> Result<String> {
+        let mut hasher = Sha256::new();
+        Self::hash_into(path, &mut hasher).await?;
+        Ok(hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect())
+    }
+
+    /// Stream a file's contents into an existing hasher, 64 KiB at a time, so
+    /// hashing a partial or complete download never requires holding the whole
+    /// (potentially multi-GB) file in memory at once.
+    async fn hash_into(path: &PathBuf, hasher: &mut Sha256) -> Result<()> {
+        use tokio::io::AsyncReadExt;
+
+        let mut file = fs::File::open(path).await?;
+        let mut buf = [0u8; 64 * 1024];
+
+        loop {
+            let read = file.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buf[..read]);
         }
 
         Ok(())
@@ -502,14 +1122,21 @@ impl ModelManager {
         }
     }
 
-    /// Cancel an ongoing download
+    /// Cancel an ongoing or queued download for a single model, without
+    /// affecting any other model's download running concurrently
     pub async fn cancel_download(&self, model_name: &str) -> Result<()> {
         log::info!("Cancelling download for model: {}", model_name);
 
-        // Set cancellation flag
+        // Set this model's cancellation flag
         {
-            let mut cancel_flag = self.cancel_download_flag.write().await;
-            *cancel_flag = Some(model_name.to_string());
+            let mut cancel_flags = self.cancel_flags.write().await;
+            cancel_flags.insert(model_name.to_string(), true);
+        }
+
+        // Drop it from the queue in case it hasn't started yet
+        {
+            let mut queue = self.download_queue.write().await;
+            queue.retain(|queued| queued != model_name);
         }
 
         // Remove from active downloads
@@ -518,20 +1145,300 @@ impl ModelManager {
             active.remove(model_name);
         }
 
-        // Update status
+        // Brief delay to let the download loop detect cancellation, flush the
+        // .part file, and stop writing to it before we inspect it below.
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        // Derive the terminal status from what's actually on disk rather than
+        // assuming `NotDownloaded`: a download cancelled mid-stream leaves a
+        // resumable `.part` file behind, which the resume logic in
+        // `stream_attempt` picks up again and `scan_models` reports as
+        // `Paused` - so reflect it as `Paused` here too.
+        let status = match get_model_by_name(model_name) {
+            Some(model_def) => {
+                let file_path = self.models_dir.join(&model_def.gguf_file);
+                let part_path = Self::part_path(&file_path);
+                match fs::metadata(&part_path).await {
+                    Ok(metadata) => ModelStatus::Paused {
+                        downloaded_bytes: metadata.len(),
+                        total_bytes: model_def.size_mb * 1024 * 1024,
+                    },
+                    Err(_) => ModelStatus::NotDownloaded,
+                }
+            }
+            None => ModelStatus::NotDownloaded,
+        };
+
         {
             let mut models = self.available_models.write().await;
             if let Some(model_info) = models.get_mut(model_name) {
-                model_info.status = ModelStatus::NotDownloaded;
+                model_info.status = status;
             }
         }
 
-        // Brief delay to let download loop detect cancellation
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        Ok(())
+    }
+
+    /// Enqueue a model for download, returning immediately. The scheduler
+    /// starts it as soon as a permit is available under `max_concurrent_downloads`.
+    pub async fn queue_download(
+        &self,
+        model_name: &str,
+        progress_callback: Option<Box<dyn Fn(u8) + Send>>,
+    ) -> Result<()> {
+        get_model_by_name(model_name).ok_or_else(|| anyhow!("Unknown model: {}", model_name))?;
+
+        {
+            let active = self.active_downloads.read().await;
+            let queue = self.download_queue.read().await;
+            if active.contains(model_name) || queue.contains(&model_name.to_string()) {
+                log::warn!("Download already queued or in progress for model: {}", model_name);
+                return Err(anyhow!("Download already queued or in progress"));
+            }
+        }
+
+        {
+            let mut cancel_flags = self.cancel_flags.write().await;
+            cancel_flags.insert(model_name.to_string(), false);
+        }
+
+        {
+            let mut queue = self.download_queue.write().await;
+            queue.push_back(model_name.to_string());
+        }
+
+        {
+            let mut models = self.available_models.write().await;
+            if let Some(model_info) = models.get_mut(model_name) {
+                model_info.status = ModelStatus::Queued;
+            }
+        }
+
+        let manager = self.clone();
+        let owned_model_name = model_name.to_string();
+        tokio::spawn(async move {
+            manager
+                .run_queued_download(&owned_model_name, progress_callback)
+                .await;
+        });
 
         Ok(())
     }
 
+    /// Worker body: wait for a concurrency permit, then run the download.
+    /// Runs inside a spawned task so `queue_download` can return immediately.
+    async fn run_queued_download(
+        &self,
+        model_name: &str,
+        progress_callback: Option<Box<dyn Fn(u8) + Send>>,
+    ) {
+        let permit = match self.download_semaphore.clone().acquire_owned().await {
+            Ok(permit) => permit,
+            Err(_) => {
+                log::error!("Download semaphore closed; dropping queued download for '{}'", model_name);
+                return;
+            }
+        };
+
+        {
+            let mut queue = self.download_queue.write().await;
+            queue.retain(|queued| queued != model_name);
+        }
+
+        // The model may have been cancelled while it was waiting on a permit;
+        // `download_model` unconditionally re-arms the cancel flag on entry,
+        // so without this check a download the user cancelled while queued
+        // would silently start anyway once a permit freed up.
+        {
+            let cancel_flags = self.cancel_flags.read().await;
+            if cancel_flags.get(model_name).copied().unwrap_or(false) {
+                log::info!(
+                    "Skipping queued download for '{}': cancelled while waiting for a permit",
+                    model_name
+                );
+                drop(permit);
+                return;
+            }
+        }
+
+        if let Err(e) = self.download_model(model_name, progress_callback).await {
+            log::error!("Queued download for '{}' failed: {}", model_name, e);
+        }
+
+        drop(permit);
+    }
+
+    /// Snapshot of the download scheduler: the ordered queue plus the in-flight set
+    pub async fn download_queue_status(&self) -> DownloadQueueStatus {
+        DownloadQueueStatus {
+            queued: self.download_queue.read().await.iter().cloned().collect(),
+            in_flight: self.active_downloads.read().await.clone(),
+        }
+    }
+
+    /// Unix timestamp (seconds) the last repair/scrub pass finished, if one has run
+    pub async fn last_scrub(&self) -> Option<u64> {
+        *self.last_scrub.read().await
+    }
+
+    /// Spawn the background repair task that re-verifies `Available` models on
+    /// `repair_interval` and self-heals anything it finds corrupted. Fire-and-forget:
+    /// the returned handle is dropped, the task keeps running for the manager's lifetime.
+    fn spawn_repair_loop(&self) {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(manager.repair_interval);
+            // The first tick fires immediately; skip it so we don't double up with
+            // the scan `init` already performed.
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if let Err(e) = manager.repair_models().await {
+                    log::error!("Background repair pass failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Re-verify every `Available` model and self-heal anything that's corrupted.
+    /// Can be called directly for an on-demand scrub, or left to the background task.
+    pub async fn repair_models(&self) -> Result<()> {
+        log::info!("Starting model repair pass");
+        let start = std::time::Instant::now();
+
+        let model_names: Vec<String> = self.available_models.read().await.keys().cloned().collect();
+        let client = Client::new();
+
+        for (i, model_name) in model_names.iter().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(REPAIR_THROTTLE_DELAY).await;
+            }
+
+            let Some(info) = self.get_model_info(model_name).await else {
+                continue;
+            };
+            if info.status != ModelStatus::Available {
+                continue;
+            }
+
+            let corruption = match self.validate_gguf_file(&info.path).await {
+                Err(e) => Some(format!("GGUF validation failed: {}", e)),
+                Ok(()) => match Self::hash_file(&info.path).await {
+                    Ok(computed) => {
+                        let model_def = get_model_by_name(model_name);
+                        match model_def {
+                            Some(def) if computed != def.expected_sha256 => {
+                                Some(format!("hash mismatch: computed {}, expected {}", computed, def.expected_sha256))
+                            }
+                            _ => None,
+                        }
+                    }
+                    Err(e) => Some(format!("failed to hash file: {}", e)),
+                },
+            };
+
+            match corruption {
+                None => {
+                    let now = Self::now_unix();
+                    let mut models = self.available_models.write().await;
+                    if let Some(model_info) = models.get_mut(model_name) {
+                        model_info.last_verified = Some(now);
+                    }
+                }
+                Some(reason) => {
+                    log::warn!("Model '{}' failed repair verification: {}", model_name, reason);
+                    self.handle_corrupt_model(&client, model_name, &info).await;
+                }
+            }
+        }
+
+        {
+            let mut last_scrub = self.last_scrub.write().await;
+            *last_scrub = Some(Self::now_unix());
+        }
+
+        log::info!("Model repair pass complete in {:?}", start.elapsed());
+        Ok(())
+    }
+
+    /// Mark a model corrupted and, if enabled, re-enqueue a download for it
+    /// once its source URL is confirmed reachable
+    async fn handle_corrupt_model(&self, client: &Client, model_name: &str, info: &ModelInfo) {
+        let file_size = fs::metadata(&info.path)
+            .await
+            .map(|m| m.len() / (1024 * 1024))
+            .unwrap_or(0);
+
+        {
+            let mut models = self.available_models.write().await;
+            if let Some(model_info) = models.get_mut(model_name) {
+                model_info.status = ModelStatus::Corrupted {
+                    file_size,
+                    expected_min_size: info.size_mb,
+                    computed_sha256: None,
+                    expected_sha256: None,
+                };
+            }
+        }
+
+        if !self.auto_redownload {
+            return;
+        }
+
+        let Some(model_def) = get_model_by_name(model_name) else {
+            return;
+        };
+
+        // Probe the primary URL and every mirror, same as `download_model`'s
+        // own failover order - a down primary shouldn't abandon a model that
+        // a healthy mirror could still repair.
+        let mut candidate_urls: Vec<&String> = Vec::with_capacity(1 + model_def.mirror_urls.len());
+        candidate_urls.push(&model_def.download_url);
+        candidate_urls.extend(model_def.mirror_urls.iter());
+
+        let mut reachable = false;
+        for url in candidate_urls {
+            let ok = client
+                .head(url)
+                .send()
+                .await
+                .map(|resp| resp.status().is_success())
+                .unwrap_or(false);
+            if ok {
+                reachable = true;
+                break;
+            }
+        }
+
+        if !reachable {
+            log::warn!(
+                "Model '{}' is corrupted but its source and all mirrors are unreachable; leaving it for manual repair",
+                model_name
+            );
+            return;
+        }
+
+        {
+            let mut models = self.available_models.write().await;
+            if let Some(model_info) = models.get_mut(model_name) {
+                model_info.status = ModelStatus::Repairing;
+            }
+        }
+
+        log::info!("Re-enqueueing download to repair model '{}'", model_name);
+        if let Err(e) = self.queue_download(model_name, None).await {
+            log::error!("Failed to re-enqueue repair download for '{}': {}", model_name, e);
+        }
+    }
+
+    /// Current Unix timestamp in seconds
+    fn now_unix() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    }
+
     /// Delete a corrupted or available model file
     pub async fn delete_model(&self, model_name: &str) -> Result<()> {
         log::info!("Deleting model: {}", model_name);
@@ -540,12 +1447,18 @@ impl ModelManager {
             .ok_or_else(|| anyhow!("Unknown model: {}", model_name))?;
 
         let file_path = self.models_dir.join(&model_def.gguf_file);
+        let part_path = Self::part_path(&file_path);
 
         if file_path.exists() {
             fs::remove_file(&file_path).await?;
             log::info!("Deleted model file: {}", file_path.display());
         }
 
+        if part_path.exists() {
+            fs::remove_file(&part_path).await?;
+            log::info!("Deleted partial download: {}", part_path.display());
+        }
+
         // Update status
         {
             let mut models = self.available_models.write().await;
@@ -562,3 +1475,64 @@ impl ModelManager {
         self.models_dir.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_content_range_value_parses_well_formed_header() {
+        assert_eq!(
+            ModelManager::parse_content_range_value("bytes 1000-1999/2000"),
+            Some((1000, 2000))
+        );
+    }
+
+    #[test]
+    fn parse_content_range_value_rejects_missing_bytes_prefix() {
+        assert_eq!(ModelManager::parse_content_range_value("1000-1999/2000"), None);
+    }
+
+    #[test]
+    fn parse_content_range_value_rejects_missing_total() {
+        assert_eq!(ModelManager::parse_content_range_value("bytes 1000-1999"), None);
+    }
+
+    #[test]
+    fn parse_content_range_value_rejects_missing_range_separator() {
+        assert_eq!(ModelManager::parse_content_range_value("bytes 1999/2000"), None);
+    }
+
+    #[test]
+    fn parse_content_range_value_rejects_non_numeric_fields() {
+        assert_eq!(
+            ModelManager::parse_content_range_value("bytes abc-1999/2000"),
+            None
+        );
+        assert_eq!(
+            ModelManager::parse_content_range_value("bytes 1000-1999/xyz"),
+            None
+        );
+    }
+
+    #[test]
+    fn backoff_delay_doubles_until_the_cap() {
+        assert!(ModelManager::backoff_delay(0) >= RETRY_BASE_DELAY);
+        assert!(ModelManager::backoff_delay(0) < RETRY_BASE_DELAY + Duration::from_secs(1));
+
+        let third = ModelManager::backoff_delay(3);
+        assert!(third >= RETRY_BASE_DELAY * 8);
+        assert!(third < RETRY_BASE_DELAY * 8 + Duration::from_secs(1));
+    }
+
+    #[test]
+    fn backoff_delay_is_capped_for_large_attempt_numbers() {
+        // attempt is shifted by `min(6)`, so anything beyond 6 must stay capped
+        // at RETRY_MAX_DELAY plus at most 1s of jitter.
+        for attempt in [6, 10, 100, u32::MAX] {
+            let delay = ModelManager::backoff_delay(attempt);
+            assert!(delay >= RETRY_MAX_DELAY);
+            assert!(delay < RETRY_MAX_DELAY + Duration::from_secs(1));
+        }
+    }
+}